@@ -0,0 +1,451 @@
+//!
+//! # Error generation and chained-error printing
+//!
+//! The core idea: every time an error crosses a function boundary, it is
+//! wrapped in a new [`SimpleMsg`] layer carrying the message plus the
+//! `file!()`/`line!()`/`column!()` where the wrapping happened (see the `d!`
+//! and `eg!` macros). The resulting chain can then be printed top to bottom,
+//! from the outermost context down to the original cause.
+//!
+
+use std::any::Any;
+use std::fmt;
+
+/// the `Result` type used across this crate and its downstream users
+pub type Result<T> = std::result::Result<T, Box<dyn RucError>>;
+
+/// the severity of a printed diagnostic, mirroring the levels of the
+/// ecosystem `log` facade
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// unrecoverable failure, eg. anything reaching `pnk!`
+    Error,
+    /// recoverable but noteworthy
+    Warn,
+    /// informational, eg. `info!`
+    Info,
+    /// verbose diagnostic detail
+    Debug,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Error => "error",
+            Severity::Warn => "warn",
+            Severity::Info => "info",
+            Severity::Debug => "debug",
+        })
+    }
+}
+
+/// emit an already-rendered message at the given severity: through the
+/// `log` facade under the `log_bridge` feature, otherwise straight to
+/// stderr, labelled with the severity
+///
+/// under `log_bridge`, `err.code()` supplies `code` and `err`'s outermost
+/// layer supplies `file`/`line` as structured key-value fields, so callers
+/// can filter/match on them without parsing `rendered`
+fn emit(severity: Severity, err: &(impl RucError + ?Sized), rendered: impl fmt::Display) {
+    #[cfg(feature = "log_bridge")]
+    {
+        let chain = err.get_chain();
+        let outermost = chain.first();
+        let code = err.code();
+        let file = outermost.map(|m| m.file).unwrap_or("");
+        let line = outermost.map(|m| m.line).unwrap_or(0);
+        match severity {
+            Severity::Error => log::error!(code = code, file = file, line = line; "{rendered}"),
+            Severity::Warn => log::warn!(code = code, file = file, line = line; "{rendered}"),
+            Severity::Info => log::info!(code = code, file = file, line = line; "{rendered}"),
+            Severity::Debug => log::debug!(code = code, file = file, line = line; "{rendered}"),
+        }
+    }
+    #[cfg(not(feature = "log_bridge"))]
+    {
+        let _ = err;
+        eprintln!("\n[{severity}] {rendered}");
+    }
+}
+
+/// A single layer of an error chain: a message plus the source location
+/// at which it was attached.
+pub struct SimpleMsg {
+    /// the human-readable message of this layer
+    pub msg: String,
+    /// the file in which this layer was created
+    pub file: &'static str,
+    /// the line number at which this layer was created
+    pub line: u32,
+    /// the column number at which this layer was created
+    pub column: u32,
+    /// an optional machine-stable code, eg. `ERR_TIMEOUT`, distinct from
+    /// the free-text `msg`, set via `d!(code: "ERR_TIMEOUT", "...")`
+    pub code: Option<String>,
+    /// an optional typed payload, set via `d!(@@expr)`/`eg!(@@expr)`, kept
+    /// alongside the debug-formatted `msg` so display still works when no
+    /// typed payload was attached
+    payload: Option<Box<dyn Any + Send + Sync>>,
+    /// the UTC-timestamp at which this layer was created, via `ts!()`
+    pub timestamp: u64,
+}
+
+impl SimpleMsg {
+    /// create a new layer, capturing its source location and creation time
+    #[inline(always)]
+    pub fn new(msg: impl fmt::Display, file: &'static str, line: u32, column: u32) -> Self {
+        SimpleMsg {
+            msg: msg.to_string(),
+            file,
+            line,
+            column,
+            code: None,
+            payload: None,
+            timestamp: crate::ts!(),
+        }
+    }
+
+    /// create a new layer carrying a machine-stable code alongside its message
+    #[inline(always)]
+    pub fn with_code(
+        msg: impl fmt::Display,
+        code: impl fmt::Display,
+        file: &'static str,
+        line: u32,
+        column: u32,
+    ) -> Self {
+        SimpleMsg {
+            code: Some(code.to_string()),
+            ..Self::new(msg, file, line, column)
+        }
+    }
+
+    /// create a new layer carrying a typed payload alongside its
+    /// debug-formatted message
+    #[inline(always)]
+    pub fn with_payload(
+        msg: impl fmt::Display,
+        payload: Box<dyn Any + Send + Sync>,
+        file: &'static str,
+        line: u32,
+        column: u32,
+    ) -> Self {
+        SimpleMsg {
+            payload: Some(payload),
+            ..Self::new(msg, file, line, column)
+        }
+    }
+
+    /// attempt to downcast this layer's typed payload, if any, to `T`
+    pub fn payload_ref<T: 'static>(&self) -> Option<&T> {
+        self.payload.as_deref().and_then(|p| p.downcast_ref::<T>())
+    }
+}
+
+impl fmt::Debug for SimpleMsg {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SimpleMsg")
+            .field("msg", &self.msg)
+            .field("file", &self.file)
+            .field("line", &self.line)
+            .field("column", &self.column)
+            .field("code", &self.code)
+            .field("has_payload", &self.payload.is_some())
+            .field("timestamp", &self.timestamp)
+            .finish()
+    }
+}
+
+impl fmt::Display for SimpleMsg {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(code) = self.code.as_ref() {
+            write!(f, "[{}] ", code)?;
+        }
+        write!(
+            f,
+            "{}\n--> {}:{}:{}",
+            self.msg, self.file, self.line, self.column
+        )
+    }
+}
+
+/// shorten a `file!()` path down to just its trailing file name.
+///
+/// Splits manually on both `/` and `\`, rather than going through
+/// `std::path::Path::file_name`, since that mishandles foreign separators
+/// (eg. a `\`-separated path shortened on a unix host) and isn't reliable
+/// under `wasm32`. Falls back to the unshortened path if that would yield
+/// an empty string.
+pub fn short_file(path: &str) -> &str {
+    match path.rfind(['/', '\\']) {
+        Some(i) if i + 1 < path.len() => &path[i + 1..],
+        _ => path,
+    }
+}
+
+/// A boxed, chainable error.
+///
+/// Every error produced by this crate implements this trait, so callers
+/// can keep wrapping a lower-level error with extra context via `.c(d!())`
+/// without losing any of the earlier layers.
+pub trait RucError: fmt::Display + fmt::Debug {
+    /// the innermost (earliest-attached) layer of the chain, ie. the
+    /// original cause
+    fn get_lowest_module(&self) -> &SimpleMsg;
+
+    /// every layer of the chain, ordered from outermost to innermost
+    fn get_chain(&self) -> Vec<&SimpleMsg>;
+
+    /// attach a new outer layer on top of this error
+    fn c(self: Box<Self>, msg: SimpleMsg) -> Box<dyn RucError>;
+
+    /// the UTC-timestamp of the outermost (most recently attached) layer
+    fn timestamp(&self) -> u64 {
+        self.get_chain().first().map(|m| m.timestamp).unwrap_or(0)
+    }
+
+    /// print the full chain at [`Severity::Error`]: to stderr, or through
+    /// the `log` facade under the `log_bridge` feature
+    #[inline(always)]
+    fn print(&self) {
+        emit(Severity::Error, self, self);
+    }
+
+    /// print the full chain(debug form) at [`Severity::Error`]: to stderr,
+    /// or through the `log` facade under the `log_bridge` feature
+    #[inline(always)]
+    fn print_debug(&self) {
+        emit(Severity::Error, self, format!("{:#?}", self));
+    }
+
+    /// print the full chain at [`Severity::Info`] (to stderr, or through
+    /// the `log` facade under the `log_bridge` feature), eg. for errors
+    /// that were recovered from and are only reported for context
+    #[inline(always)]
+    fn print_info(&self) {
+        emit(Severity::Info, self, self);
+    }
+
+    /// print and panic
+    #[inline(always)]
+    fn print_die(&self) -> ! {
+        self.print();
+        panic!();
+    }
+
+    /// print(debug form) and panic
+    #[inline(always)]
+    fn print_die_debug(&self) -> ! {
+        self.print_debug();
+        panic!();
+    }
+
+    /// render the chain the way `rustc`/`cargo` render their diagnostics:
+    /// a bold header line per layer followed by a dim location pointer,
+    /// walking from the outermost layer down to the original cause
+    fn render_pretty(&self) -> String {
+        render::render_chain(self.get_chain())
+    }
+
+    /// print the `rustc`/`cargo`-style rendering of the chain at
+    /// [`Severity::Error`]: to stderr, or through the `log` facade under the
+    /// `log_bridge` feature
+    #[inline(always)]
+    fn print_pretty(&self) {
+        emit(Severity::Error, self, self.render_pretty());
+    }
+
+    /// print the pretty rendering and panic
+    #[inline(always)]
+    fn print_die_pretty(&self) -> ! {
+        self.print_pretty();
+        panic!();
+    }
+
+    /// the first machine-stable code found walking the chain outward, ie.
+    /// starting at the original cause and moving toward the caller
+    fn code(&self) -> Option<&str> {
+        self.get_chain()
+            .into_iter()
+            .rev()
+            .find_map(|m| m.code.as_deref())
+    }
+
+    /// every machine-stable code in the chain, ordered from outermost to
+    /// innermost, same as [`RucError::get_chain`]
+    fn codes(&self) -> Vec<&str> {
+        self.get_chain()
+            .into_iter()
+            .filter_map(|m| m.code.as_deref())
+            .collect()
+    }
+
+    /// serialize the full chain as a JSON array, outermost layer first,
+    /// each entry carrying `msg`/`file`/`line`/`column`/`code`/`timestamp`
+    #[cfg(feature = "json")]
+    fn to_json(&self) -> serde_json::Value {
+        serde_json::Value::Array(
+            self.get_chain()
+                .into_iter()
+                .map(|m| {
+                    serde_json::json!({
+                        "msg": m.msg,
+                        "file": m.file,
+                        "line": m.line,
+                        "column": m.column,
+                        "code": m.code,
+                        "timestamp": m.timestamp,
+                    })
+                })
+                .collect(),
+        )
+    }
+
+    /// print the JSON-serialized chain to stderr, eg. for log aggregators
+    #[cfg(feature = "json")]
+    #[inline(always)]
+    fn print_json(&self) {
+        eprintln!("{}", self.to_json());
+    }
+}
+
+impl dyn RucError {
+    /// search every layer of the chain for a typed payload of type `T`,
+    /// attached via `d!(@@expr)`/`eg!(@@expr)`
+    ///
+    /// kept off the trait itself (a generic method would make `RucError`
+    /// non-object-safe, breaking `Box<dyn RucError>` everywhere)
+    pub fn downcast_ref<T: 'static>(&self) -> Option<&T> {
+        self.get_chain()
+            .into_iter()
+            .find_map(|m| m.payload_ref::<T>())
+    }
+}
+
+/// The concrete error type produced by the `eg!`/`d!` macros.
+#[derive(Debug)]
+pub struct SimpleError {
+    msg: SimpleMsg,
+    cause: Option<Box<dyn RucError>>,
+}
+
+impl SimpleError {
+    /// create a new error, optionally wrapping a lower-level cause
+    #[inline(always)]
+    pub fn new(msg: SimpleMsg, cause: Option<Box<dyn RucError>>) -> Self {
+        SimpleError { msg, cause }
+    }
+}
+
+impl fmt::Display for SimpleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.msg)?;
+        if let Some(c) = self.cause.as_ref() {
+            write!(f, "\n{}", c)?;
+        }
+        Ok(())
+    }
+}
+
+impl RucError for SimpleError {
+    fn get_lowest_module(&self) -> &SimpleMsg {
+        self.cause
+            .as_ref()
+            .map(|c| c.get_lowest_module())
+            .unwrap_or(&self.msg)
+    }
+
+    fn get_chain(&self) -> Vec<&SimpleMsg> {
+        let mut chain = vec![&self.msg];
+        if let Some(c) = self.cause.as_ref() {
+            chain.extend(c.get_chain());
+        }
+        chain
+    }
+
+    fn c(self: Box<Self>, msg: SimpleMsg) -> Box<dyn RucError> {
+        Box::new(SimpleError::new(msg, Some(self)))
+    }
+}
+
+/// extension trait implementing the `.c(d!())` chaining syntax on a `Result`
+pub trait RucResult<T, E> {
+    /// attach a new outer layer of context on top of the error branch
+    fn c(self, msg: SimpleMsg) -> Result<T>;
+}
+
+impl<T, E: RucError + 'static> RucResult<T, E> for std::result::Result<T, E> {
+    #[inline(always)]
+    fn c(self, msg: SimpleMsg) -> Result<T> {
+        self.map_err(|e| Box::new(e) as Box<dyn RucError>).c(msg)
+    }
+}
+
+impl<T> RucResult<T, Box<dyn RucError>> for Result<T> {
+    #[inline(always)]
+    fn c(self, msg: SimpleMsg) -> Result<T> {
+        self.map_err(|e| e.c(msg))
+    }
+}
+
+mod render {
+    use super::SimpleMsg;
+
+    #[cfg(feature = "colored")]
+    fn use_color() -> bool {
+        use std::io::IsTerminal;
+        std::env::var_os("NO_COLOR").is_none() && std::io::stderr().is_terminal()
+    }
+
+    #[cfg(not(feature = "colored"))]
+    fn use_color() -> bool {
+        false
+    }
+
+    /// the file name shown in the location arrow: the full `file!()` path
+    /// under the `verbose_path` feature, otherwise just the trailing name
+    #[cfg(feature = "verbose_path")]
+    fn display_file(file: &str) -> &str {
+        file
+    }
+
+    #[cfg(not(feature = "verbose_path"))]
+    fn display_file(file: &str) -> &str {
+        super::short_file(file)
+    }
+
+    const BOLD_RED: &str = "\x1b[1;31m";
+    const DIM_CYAN: &str = "\x1b[2;36m";
+    const DIM: &str = "\x1b[2m";
+    const RESET: &str = "\x1b[0m";
+
+    /// render a chain of layers, outermost first, the way `rustc`/`cargo`
+    /// render a diagnostic: a bold header followed by a dim location arrow
+    pub(super) fn render_chain(chain: Vec<&SimpleMsg>) -> String {
+        let color = use_color();
+        let mut out = String::new();
+        for (i, layer) in chain.iter().enumerate() {
+            if i > 0 {
+                out.push('\n');
+            }
+            let header = match layer.code.as_deref() {
+                Some(code) => format!("error[{code}]"),
+                None => "error".to_owned(),
+            };
+            let at = crate::datetime!(layer.timestamp);
+            let file = display_file(layer.file);
+            if color {
+                out.push_str(&format!(
+                    "{BOLD_RED}{header}{RESET}{DIM}:{RESET} {}\n{DIM_CYAN} --> {}:{}:{}{RESET}\n{DIM}   = note: at {at}{RESET}",
+                    layer.msg, file, layer.line, layer.column
+                ));
+            } else {
+                out.push_str(&format!(
+                    "{header}: {}\n --> {}:{}:{}\n   = note: at {at}",
+                    layer.msg, file, layer.line, layer.column
+                ));
+            }
+        }
+        out
+    }
+}
@@ -89,14 +89,26 @@ macro_rules! alt {
     }};
 }
 
-/// print infomation only
+/// print infomation only, at `Severity::Info`
 #[macro_export]
 macro_rules! info {
     ($ops: expr) => {{
-        $ops.c($crate::d!()).map_err(|e| e.print())
+        $ops.c($crate::d!()).map_err(|e| e.print_info())
     }};
     ($ops: expr, $msg: expr) => {{
-        $ops.c($crate::d!($msg)).map_err(|e| e.print())
+        $ops.c($crate::d!($msg)).map_err(|e| e.print_info())
+    }};
+}
+
+/// print infomation as a JSON-serialized chain only, for log aggregators
+#[cfg(feature = "json")]
+#[macro_export]
+macro_rules! info_json {
+    ($ops: expr) => {{
+        $ops.c($crate::d!()).map_err(|e| e.print_json())
+    }};
+    ($ops: expr, $msg: expr) => {{
+        $ops.c($crate::d!($msg)).map_err(|e| e.print_json())
     }};
 }
 
@@ -122,12 +134,25 @@ macro_rules! info_omit {
 /// print debug-info, eg: modular and file path, line number ...
 #[macro_export]
 macro_rules! d {
-    ($err: expr) => {{
-        $crate::err::SimpleMsg::new($err, file!(), line!(), column!())
+    (code: $code: expr, $err: expr) => {{
+        $crate::err::SimpleMsg::with_code($err, $code, file!(), line!(), column!())
+    }};
+    (@@$err: expr) => {{
+        let __v = $err;
+        $crate::err::SimpleMsg::with_payload(
+            format!("{:?}", __v),
+            Box::new(__v),
+            file!(),
+            line!(),
+            column!(),
+        )
     }};
     (@$err: expr) => {{
         $crate::d!(format!("{:?}", $err))
     }};
+    ($err: expr) => {{
+        $crate::err::SimpleMsg::new($err, file!(), line!(), column!())
+    }};
     () => {{
         $crate::d!("...")
     }};
@@ -167,7 +192,10 @@ macro_rules! datetime {
 #[cfg(not(target_arch = "wasm32"))]
 #[inline(always)]
 pub fn gen_datetime(ts: i64) -> String {
-    time::OffsetDateTime::from_unix_timestamp(ts).format("%F %T")
+    time::OffsetDateTime::from_unix_timestamp(ts)
+        .ok()
+        .and_then(|d| d.format(&time::format_description::well_known::Rfc3339).ok())
+        .unwrap_or_default()
 }
 
 #[cfg(target_arch = "wasm32")]
@@ -191,11 +219,12 @@ macro_rules! die {
 /// Print log, and panic
 #[macro_export]
 macro_rules! pnk {
-    ($ops: expr) => {{
-        $ops.c($crate::d!()).unwrap_or_else(|e| e.print_die())
+    (#$ops: expr) => {{
+        $ops.c($crate::d!()).unwrap_or_else(|e| e.print_die_pretty())
     }};
-    ($ops: expr, $msg: expr) => {{
-        $ops.c($crate::d!($msg)).unwrap_or_else(|e| e.print_die())
+    (#$ops: expr, $msg: expr) => {{
+        $ops.c($crate::d!($msg))
+            .unwrap_or_else(|e| e.print_die_pretty())
     }};
     (@$ops: expr) => {{
         $ops.c($crate::d!()).unwrap_or_else(|e| e.print_die_debug())
@@ -204,6 +233,12 @@ macro_rules! pnk {
         $ops.c($crate::d!($msg))
             .unwrap_or_else(|e| e.print_die_debug())
     }};
+    ($ops: expr) => {{
+        $ops.c($crate::d!()).unwrap_or_else(|e| e.print_die())
+    }};
+    ($ops: expr, $msg: expr) => {{
+        $ops.c($crate::d!($msg)).unwrap_or_else(|e| e.print_die())
+    }};
 }
 
 /// Sleep in milliseconds
@@ -217,6 +252,16 @@ macro_rules! sleep_ms {
 /// Generate error with debug info
 #[macro_export]
 macro_rules! eg {
+    (code: $code: expr, $msg: expr) => {{
+        Box::new($crate::err::SimpleError::new(
+            $crate::d!(code: $code, $msg),
+            None,
+        )) as Box<dyn $crate::err::RucError>
+    }};
+    (@@$msg: expr) => {{
+        Box::new($crate::err::SimpleError::new($crate::d!(@@$msg), None))
+            as Box<dyn $crate::err::RucError>
+    }};
     ($msg: expr) => {{
         Box::new($crate::err::SimpleError::new($crate::d!($msg), None))
             as Box<dyn $crate::err::RucError>
@@ -258,6 +303,117 @@ mod tests {
         pnk!(@t_display_style_inner());
     }
 
+    #[test]
+    #[should_panic]
+    fn t_display_style_pretty() {
+        pnk!(#t_display_style_inner());
+    }
+
+    #[test]
+    fn t_render_pretty() {
+        let e = t_display_style_inner().unwrap_err();
+        let rendered = e.render_pretty();
+        assert!(rendered.contains("The final error message!"));
+        assert!(rendered.contains("--> "));
+    }
+
+    #[test]
+    fn t_error_code() {
+        let l1 = || -> Result<()> { Err(eg!(code: "ERR_INNER", "the original cause")) };
+        let l2 = || -> Result<()> { l1().c(d!(code: "ERR_OUTER", "wrapped with more context")) };
+        let e = l2().unwrap_err();
+
+        assert_eq!(Some("ERR_INNER"), e.code());
+        assert_eq!(vec!["ERR_OUTER", "ERR_INNER"], e.codes());
+        assert!(e.render_pretty().contains("error[ERR_OUTER]"));
+    }
+
+    #[test]
+    fn t_typed_cause() {
+        #[derive(Debug, Eq, PartialEq)]
+        struct CustomErr(i32);
+
+        let l1 = || -> Result<()> { Err(eg!(@@CustomErr(-1))) };
+        let l2 = || -> Result<()> { l1().c(d!()) };
+        let e = l2().unwrap_err();
+
+        assert_eq!(Some(&CustomErr(-1)), e.downcast_ref::<CustomErr>());
+        assert!(e.render_pretty().contains("CustomErr(-1)"));
+    }
+
+    #[test]
+    fn t_typed_cause_single_eval() {
+        use std::sync::atomic::{AtomicI32, Ordering};
+
+        #[derive(Debug, Eq, PartialEq)]
+        struct Counted(i32);
+
+        static NEXT: AtomicI32 = AtomicI32::new(0);
+        fn next() -> Counted {
+            Counted(NEXT.fetch_add(1, Ordering::SeqCst))
+        }
+
+        let e: Box<dyn RucError> = eg!(@@next());
+
+        // the debug string baked into `msg` and the boxed payload must
+        // come from the same evaluation of `next()`
+        assert!(e.render_pretty().contains(&format!(
+            "{:?}",
+            e.downcast_ref::<Counted>().unwrap()
+        )));
+        assert_eq!(1, NEXT.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn t_timestamp() {
+        let before = ts!();
+        let e = t_display_style_inner().unwrap_err();
+        let after = ts!();
+
+        assert!(before <= e.timestamp() && e.timestamp() <= after);
+        assert!(e.render_pretty().contains("= note: at "));
+    }
+
+    #[test]
+    fn t_short_file() {
+        assert_eq!("lib.rs", short_file("src/lib.rs"));
+        assert_eq!("lib.rs", short_file(r"src\nested\lib.rs"));
+        assert_eq!("lib.rs", short_file("lib.rs"));
+        assert_eq!("src/", short_file("src/"));
+
+        let e = t_display_style_inner().unwrap_err();
+        #[cfg(not(feature = "verbose_path"))]
+        assert!(e.render_pretty().contains("--> lib.rs:"));
+        #[cfg(feature = "verbose_path")]
+        assert!(e.render_pretty().contains("--> src/lib.rs:"));
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn t_to_json() {
+        let l1 = || -> Result<()> { Err(eg!(code: "ERR_INNER", "the original cause")) };
+        let l2 = || -> Result<()> { l1().c(d!(code: "ERR_OUTER", "wrapped with more context")) };
+        let e = l2().unwrap_err();
+
+        let json = e.to_json();
+        let layers = json.as_array().unwrap();
+        assert_eq!(2, layers.len());
+        assert_eq!("ERR_OUTER", layers[0]["code"]);
+        assert_eq!("ERR_INNER", layers[1]["code"]);
+        assert_eq!("the original cause", layers[1]["msg"]);
+
+        let _ = info_json!(Err::<u8, _>(eg!()));
+    }
+
+    #[test]
+    fn t_severity() {
+        assert_eq!("error", Severity::Error.to_string());
+        assert_eq!("info", Severity::Info.to_string());
+
+        let _ = info!(Err::<u8, _>(eg!()));
+        t_display_style_inner().unwrap_err().print_info();
+    }
+
     #[test]
     fn t_map() {
         let s1 = map! {1 => 2, 2 => 4};